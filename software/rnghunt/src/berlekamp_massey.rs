@@ -14,12 +14,17 @@ pub fn berlekamp_massey(s: &BinaryVector) -> BinaryPolynomial {
     for n in 0..s.n {
         let x = s.slice((s.n-n-1)..(s.n-n+l));
         c.coefficients.n = l+1;
-        if c.eval(&x) == 1 {
+
+        // eval() requires its operand to be packed to exactly the same word count as
+        // the polynomial it's evaluating, so re-pack c down to its current (narrower)
+        // length rather than evaluating against its full, oversized backing data.
+        let candidate = BinaryPolynomial { coefficients: c.coefficients.slice(0..l+1) };
+        if candidate.eval(&x) == 1 {
             let t = c.clone();
             c.coefficients.n = s.n;
             b.coefficients.n = s.n;
             let offset = ((n as i32) - m) as usize;
-            c.coefficients ^= &(&b.coefficients >> offset).slice(0..s.n);
+            c.coefficients ^= (&b.coefficients >> offset).slice(0..s.n);
             if l <= n/2 {
                 l = n + 1 - l;
                 m = n as i32;
@@ -43,6 +43,414 @@ impl fmt::Display for BinaryPolynomial {
     }
 }
 
+/// Add (XOR) two polynomials of possibly different lengths, aligning them by degree
+/// rather than by coefficient vector index.
+fn poly_xor(a: &BinaryPolynomial, b: &BinaryPolynomial) -> BinaryPolynomial {
+    let degree = {
+        let (da, db) = (a.degree(), b.degree());
+        if da > db { da } else { db }
+    };
+    if degree < 0 {
+        return BinaryPolynomial::from_coefficients(&[0]);
+    }
+    let n = (degree + 1) as usize;
+    let mut out = BinaryPolynomial::from_coefficients(&vec![0u8; n]);
+    for bit in a.coefficients.ones() {
+        let degree = a.coefficients.n - 1 - bit;
+        out.coefficients.flip(n - 1 - degree);
+    }
+    for bit in b.coefficients.ones() {
+        let degree = b.coefficients.n - 1 - bit;
+        out.coefficients.flip(n - 1 - degree);
+    }
+    out
+}
+
+/// The formal derivative of `f` over GF(2): `d/dx (sum a_i x^i) = sum_{i odd} a_i x^(i-1)`,
+/// i.e. every even-power term vanishes. Each odd-degree term is moved down by one degree.
+fn derivative(f: &BinaryPolynomial) -> BinaryPolynomial {
+    let n = f.coefficients.n;
+    let mut out = BinaryPolynomial::from_coefficients(&vec![0u8; n]);
+    for bit in f.coefficients.ones() {
+        let degree = n - 1 - bit;
+        if degree % 2 == 1 {
+            out.coefficients.set(n - 1 - (degree - 1), true);
+        }
+    }
+    out
+}
+
+/// Take the square root of `f`, which must have only even-degree terms (as is always the
+/// case over GF(2) when `f`'s derivative is zero), by halving every exponent.
+fn sqrt_even(f: &BinaryPolynomial) -> BinaryPolynomial {
+    let degree = f.degree();
+    if degree < 0 {
+        return BinaryPolynomial::from_coefficients(&[0]);
+    }
+    let n = (degree / 2) as usize + 1;
+    let mut out = BinaryPolynomial::from_coefficients(&vec![0u8; n]);
+    for bit in f.coefficients.ones() {
+        let half = (f.coefficients.n - 1 - bit) / 2;
+        out.coefficients.flip(n - 1 - half);
+    }
+    out
+}
+
+/// Square-free decomposition of `f`, as `(factor, multiplicity)` pairs, via Yun's
+/// algorithm adapted to characteristic 2: the usual `gcd(f, f')`-based peeling handles
+/// every factor whose multiplicity isn't a multiple of 2, and whatever's left over
+/// (`c` below) is itself a perfect square, recovered by halving exponents and recursing
+/// with doubled multiplicities.
+fn squarefree_decompose(f: &BinaryPolynomial) -> Vec<(BinaryPolynomial, usize)> {
+    if f.degree() <= 0 {
+        return Vec::new();
+    }
+
+    let fprime = derivative(f);
+    if fprime.degree() == -1 {
+        let g = sqrt_even(f);
+        return squarefree_decompose(&g).into_iter().map(|(p, m)| (p, m * 2)).collect();
+    }
+
+    let mut result = Vec::new();
+    let mut c = f.gcd(&fprime);
+    let (mut w, _) = f.divmod(&c);
+    let mut mult = 1;
+
+    while w.degree() > 0 {
+        let y = w.gcd(&c);
+        let (factor, _) = w.divmod(&y);
+        if factor.degree() > 0 {
+            result.push((factor, mult));
+        }
+        w = y.clone();
+        let (next_c, _) = c.divmod(&y);
+        c = next_c;
+        mult += 1;
+    }
+
+    if c.degree() > 0 {
+        let g = sqrt_even(&c);
+        result.extend(squarefree_decompose(&g).into_iter().map(|(p, m)| (p, m * 2)));
+    }
+
+    result
+}
+
+/// Return the distinct prime factors of `n`, via simple trial division.
+///
+/// `n` here is a polynomial degree, not `2^n - 1` as used by `get_factors`, so it's
+/// small enough that trial division is plenty fast.
+fn distinct_prime_factors(n: usize) -> Vec<usize> {
+    let mut factors = Vec::new();
+    let mut n = n;
+    let mut p = 2;
+    while p * p <= n {
+        if n % p == 0 {
+            factors.push(p);
+            while n % p == 0 {
+                n /= p;
+            }
+        }
+        p += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+/// Read `p`'s coefficients (degrees 0..n) into a plain length-`n` BinaryVector, indexed
+/// by degree rather than the polynomial's own MSbit-first convention. Used to build rows
+/// of the Berlekamp matrix out of reduced polynomials.
+fn poly_to_row(p: &BinaryPolynomial, n: usize) -> BinaryVector {
+    let mut row = BinaryVector::from_bits(&vec![0u8; n]);
+    for bit in p.coefficients.ones() {
+        let degree = p.coefficients.n - 1 - bit;
+        if degree < n {
+            row.set(degree, true);
+        }
+    }
+    row
+}
+
+/// The inverse of `poly_to_row`: turn a degree-indexed row back into a BinaryPolynomial
+/// of the same length (and hence same degree-to-index convention) as `like`.
+fn row_to_poly(row: &BinaryVector, like: &BinaryPolynomial) -> BinaryPolynomial {
+    let mut out = BinaryPolynomial::from_coefficients(&vec![0u8; like.coefficients.n]);
+    let outn = out.coefficients.n;
+    for degree in row.ones() {
+        out.coefficients.set(outn - 1 - degree, true);
+    }
+    out
+}
+
+/// Build the rows of the Berlekamp `Q + I` matrix for degree-`n` polynomial `f`: row `k`
+/// is `x^(2k) mod f`, with the diagonal (identity) bit toggled in afterwards.
+fn berlekamp_matrix(f: &BinaryPolynomial, n: usize) -> Vec<BinaryVector> {
+    let offset = (64 - (f.coefficients.n % 64)) % 64;
+
+    // "1", at the same length as f.
+    let mut cur = BinaryPolynomial::from_coefficients(&vec![0u8; f.coefficients.n]);
+    cur.coefficients.data[f.coefficients.data.len()-1] = 1<<offset;
+
+    // x, at the same length as f.
+    let mut x = BinaryPolynomial::from_coefficients(&vec![0u8; f.coefficients.n]);
+    x.coefficients.data[f.coefficients.data.len()-1] = 2<<offset;
+
+    // x^2 mod f, used to step from x^(2k) to x^(2(k+1)) mod f at each row.
+    let x2 = x.modmult(&x, f);
+
+    let mut rows = Vec::with_capacity(n);
+    for k in 0..n {
+        let mut row = poly_to_row(&cur, n);
+        row.flip(k);
+        rows.push(row);
+        cur = cur.modmult(&x2, f);
+    }
+    rows
+}
+
+/// Compute a basis for the left null space of the matrix whose rows are `rows`, i.e. all
+/// `v` such that XORing together the subset of `rows` selected by `v`'s set bits gives
+/// zero. Implemented by Gaussian elimination on `rows` augmented with an identity block
+/// that tracks, for each row reduced to zero, which combination of the original rows
+/// produced it.
+fn left_null_space(rows: &[BinaryVector]) -> Vec<BinaryVector> {
+    let n = rows[0].n;
+    let mut aug: Vec<BinaryVector> = rows.iter().enumerate().map(|(k, row)| {
+        let mut combined = BinaryVector::from_bits(&vec![0u8; 2*n]);
+        for bit in row.ones() {
+            combined.set(bit, true);
+        }
+        combined.set(n + k, true);
+        combined
+    }).collect();
+
+    let mut pivot_row = 0;
+    for col in 0..n {
+        if let Some(sel) = (pivot_row..aug.len()).find(|&i| aug[i].get(col)) {
+            aug.swap(pivot_row, sel);
+            for i in 0..aug.len() {
+                if i != pivot_row && aug[i].get(col) {
+                    let pivot = aug[pivot_row].clone();
+                    aug[i] ^= pivot;
+                }
+            }
+            pivot_row += 1;
+        }
+    }
+
+    aug[pivot_row..].iter().map(|row| row.slice(n..2*n)).collect()
+}
+
+/// Factor a known-square-free polynomial via Berlekamp's algorithm.
+fn factor_squarefree(f: &BinaryPolynomial) -> Vec<BinaryPolynomial> {
+    let n = f.degree() as usize;
+
+    let rows = berlekamp_matrix(f, n);
+    let basis = left_null_space(&rows);
+
+    // 1, at the same length as f, needed to turn each null-space vector v into the
+    // two candidate splitters v and v+1.
+    let offset = (64 - (f.coefficients.n % 64)) % 64;
+    let mut one = BinaryPolynomial::from_coefficients(&vec![0u8; f.coefficients.n]);
+    one.coefficients.data[f.coefficients.data.len()-1] = 1<<offset;
+
+    let mut factors = vec![f.clone()];
+    for v in &basis {
+        if factors.len() == basis.len() {
+            break;
+        }
+
+        let vp = row_to_poly(v, f);
+        let vp1 = poly_xor(&vp, &one);
+
+        let mut refined = Vec::new();
+        for factor in factors {
+            // Degree-1 factors are already irreducible.
+            if factor.degree() == 1 {
+                refined.push(factor);
+                continue;
+            }
+            let g1 = factor.gcd(&vp);
+            if g1.degree() > 0 && g1.degree() < factor.degree() {
+                refined.push(g1);
+                refined.push(factor.gcd(&vp1));
+            } else {
+                // This basis vector doesn't split this factor.
+                refined.push(factor);
+            }
+        }
+        factors = refined;
+    }
+
+    factors
+}
+
+/// Carryless multiply of two 64-bit words, returning `(low, high)` of the 128-bit result.
+/// Uses the hardware `pclmulqdq` instruction when the crate is built for it, since it
+/// does this in one cycle versus 64 iterations of the software fallback below.
+#[cfg(all(target_arch = "x86_64", target_feature = "pclmulqdq"))]
+fn clmul64(a: u64, b: u64) -> (u64, u64) {
+    use core::arch::x86_64::*;
+    unsafe {
+        let va = _mm_set_epi64x(0, a as i64);
+        let vb = _mm_set_epi64x(0, b as i64);
+        let prod = _mm_clmulepi64_si128(va, vb, 0);
+        let lo = _mm_cvtsi128_si64(prod) as u64;
+        let hi = _mm_extract_epi64(prod, 1) as u64;
+        (lo, hi)
+    }
+}
+
+/// Software fallback for `clmul64`: add in `b` shifted by `i` for every set bit `i` of `a`.
+#[cfg(not(all(target_arch = "x86_64", target_feature = "pclmulqdq")))]
+fn clmul64(a: u64, b: u64) -> (u64, u64) {
+    let mut lo = 0u64;
+    let mut hi = 0u64;
+    for i in 0..64 {
+        if (a >> i) & 1 == 1 {
+            lo ^= if i == 0 { b } else { b << i };
+            hi ^= if i == 0 { 0 } else { b >> (64 - i) };
+        }
+    }
+    (lo, hi)
+}
+
+/// XOR `src` into `dst` at word offset `offset`, growing neither — `dst` must already be
+/// long enough.
+fn xor_words_at(dst: &mut [u64], src: &[u64], offset: usize) {
+    for (i, &w) in src.iter().enumerate() {
+        dst[offset + i] ^= w;
+    }
+}
+
+/// Elementwise XOR of two (possibly different-length) word vectors, zero-extending the
+/// shorter one.
+fn xor_words(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut out = vec![0u64; a.len().max(b.len())];
+    xor_words_at(&mut out, a, 0);
+    xor_words_at(&mut out, b, 0);
+    out
+}
+
+/// Schoolbook carryless multiply of two word vectors (one `clmul64` per word pair).
+fn mul_words_schoolbook(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut out = vec![0u64; a.len() + b.len()];
+    for (i, &aw) in a.iter().enumerate() {
+        if aw == 0 {
+            continue;
+        }
+        for (j, &bw) in b.iter().enumerate() {
+            if bw == 0 {
+                continue;
+            }
+            let (lo, hi) = clmul64(aw, bw);
+            out[i+j] ^= lo;
+            out[i+j+1] ^= hi;
+        }
+    }
+    out
+}
+
+/// Above this many words per operand, switch from schoolbook to Karatsuba multiplication.
+const KARATSUBA_WORDS: usize = 8;
+
+/// Multiply two word vectors (bit `j` of word `i` is the coefficient of `x^(64i+j)`) as
+/// GF(2) polynomials, via carryless word multiplication and Karatsuba recursion above
+/// `KARATSUBA_WORDS`. Since we're in characteristic 2, addition and subtraction are both
+/// XOR, so unlike integer Karatsuba there's no sign bookkeeping to do.
+fn mul_words(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let n = a.len().max(b.len());
+    if n <= KARATSUBA_WORDS {
+        return mul_words_schoolbook(a, b);
+    }
+
+    let m = (n + 1) / 2;
+    let a0 = &a[..a.len().min(m)];
+    let a1 = if a.len() > m { &a[m..] } else { &[] };
+    let b0 = &b[..b.len().min(m)];
+    let b1 = if b.len() > m { &b[m..] } else { &[] };
+
+    let z0 = mul_words(a0, b0);
+    let z2 = mul_words(a1, b1);
+    let a01 = xor_words(a0, a1);
+    let b01 = xor_words(b0, b1);
+    let mut z1 = mul_words(&a01, &b01);
+    xor_words_at(&mut z1, &z0, 0);
+    xor_words_at(&mut z1, &z2, 0);
+
+    let mut result = vec![0u64; 2*n];
+    xor_words_at(&mut result, &z0, 0);
+    xor_words_at(&mut result, &z1, m);
+    xor_words_at(&mut result, &z2, 2*m);
+    result
+}
+
+/// Pack `p`'s coefficients into words with bit `j` of word `i` being the coefficient of
+/// `x^(64i+j)` — the opposite bit order from `BinaryVector`'s own MSbit-first packing,
+/// but the natural one for `mul_words`'s word-at-a-time carryless multiplication.
+fn poly_to_ascending_words(p: &BinaryPolynomial) -> Vec<u64> {
+    let degree = p.degree();
+    if degree < 0 {
+        return Vec::new();
+    }
+    let mut words = vec![0u64; degree as usize / 64 + 1];
+    for bit in p.coefficients.ones() {
+        let d = p.coefficients.n - 1 - bit;
+        words[d/64] |= 1u64 << (d % 64);
+    }
+    words
+}
+
+/// The inverse of `poly_to_ascending_words`.
+fn ascending_words_to_poly(words: &[u64]) -> BinaryPolynomial {
+    let mut degree = -1isize;
+    for (i, &w) in words.iter().enumerate() {
+        if w != 0 {
+            degree = (i*64 + 63 - w.leading_zeros() as usize) as isize;
+        }
+    }
+    if degree < 0 {
+        return BinaryPolynomial::from_coefficients(&[0]);
+    }
+
+    let n = degree as usize + 1;
+    let mut out = BinaryPolynomial::from_coefficients(&vec![0u8; n]);
+    for (i, &word) in words.iter().enumerate() {
+        let mut w = word;
+        while w != 0 {
+            let d = i*64 + w.trailing_zeros() as usize;
+            if d < n {
+                out.coefficients.flip(n - 1 - d);
+            }
+            w &= w - 1;
+        }
+    }
+    out
+}
+
+/// Multiply two polynomials without reducing modulo anything, used for the cofactor
+/// bookkeeping in `BinaryPolynomial::modinv`.
+fn mul_plain(a: &BinaryPolynomial, b: &BinaryPolynomial) -> BinaryPolynomial {
+    let (degree_a, degree_b) = (a.degree(), b.degree());
+    if degree_a < 0 || degree_b < 0 {
+        return BinaryPolynomial::from_coefficients(&[0]);
+    }
+    let n = (degree_a + degree_b + 1) as usize;
+    let mut out = BinaryPolynomial::from_coefficients(&vec![0u8; n]);
+    for abit in a.coefficients.ones() {
+        let da = a.coefficients.n - 1 - abit;
+        for bbit in b.coefficients.ones() {
+            let db = b.coefficients.n - 1 - bbit;
+            let degree = da + db;
+            out.coefficients.flip(n - 1 - degree);
+        }
+    }
+    out
+}
+
 impl BinaryPolynomial {
     /// Make a new BinaryPolynomial from the provided coefficients.
     ///
@@ -84,7 +492,37 @@ impl BinaryPolynomial {
         parity
     }
 
+    /// Multiply by `g` without reducing modulo anything, via carryless word multiplication
+    /// (using the hardware `pclmulqdq` instruction where available) and Karatsuba
+    /// recursion for large operands. `self.degree() + g.degree()` is the degree of the
+    /// result; unlike `modmult`, `self` and `g` don't need matching lengths.
+    pub fn mul(&self, g: &BinaryPolynomial) -> BinaryPolynomial {
+        let a = poly_to_ascending_words(self);
+        let b = poly_to_ascending_words(g);
+        if a.is_empty() || b.is_empty() {
+            return BinaryPolynomial::from_coefficients(&[0]);
+        }
+        ascending_words_to_poly(&mul_words(&a, &b))
+    }
+
+    /// Reduce this polynomial modulo `p`, i.e. the remainder of `self / p`.
+    pub fn reduce_mod(&self, p: &BinaryPolynomial) -> BinaryPolynomial {
+        let (_, r) = self.divmod(p);
+        r
+    }
+
     /// Evaluate the product with `g` mod `p`, returning the result as a new BinaryPolynomial.
+    ///
+    /// NOTE: this deliberately stays the original bit-at-a-time implementation rather than
+    /// becoming `self.mul(g).reduce_mod(p)`. `mul`/`reduce_mod` return polynomials packed
+    /// to whatever width their own result needs, whereas every caller here (`modexp`,
+    /// `is_irreducible`, `check_integer`, ...) relies on `modmult`'s result coming back
+    /// packed to exactly `p.coefficients.n` coefficients, matching `self`/`g`/`p`, so it can be fed
+    /// straight back in as an operand of the next `modmult`/`modexp` step. Swapping the body
+    /// over would need those call sites reworked to re-pack the remainder to `p`'s width (as
+    /// `modinv` already does for its own return value) before this can be attempted safely.
+    /// `modmult` is kept around as-is and used as the correctness oracle for `mul`/`reduce_mod`
+    /// in the tests below.
     pub fn modmult(&self, g: &BinaryPolynomial, p: &BinaryPolynomial) -> BinaryPolynomial {
         // We have:
         // self = f(x) = a_n x^n + ... + a_1 x + a_0
@@ -109,14 +547,13 @@ impl BinaryPolynomial {
         let mut gs = g.clone();
 
         // Prepare a result the same size as p but zeroed
-        let mut r = p.clone();
-        r.coefficients ^= &p.coefficients;
+        let mut r = BinaryPolynomial::from_coefficients(&vec![0u8; p.coefficients.n]);
 
         // For each bit set in self, starting at the lowest...
         for bit in 1..((self.degree()+2) as usize) {
             // If this bit is set, we accumulate the current shifted version of g
             if self.coefficients[self.coefficients.n - bit] {
-                r.coefficients ^= &gs.coefficients;
+                r.coefficients ^= gs.coefficients.clone();
             }
 
             // Multiply gs by x. We have to increase the bit length as well.
@@ -125,19 +562,100 @@ impl BinaryPolynomial {
 
             // Mod p(x)
             if gs.coefficients[gs.coefficients.n - degree_p - 1] {
-                gs.coefficients ^= &p.coefficients;
+                gs.coefficients ^= p.coefficients.clone();
             }
         }
 
         r
     }
 
+    /// Divide `self` by `d`, returning `(quotient, remainder)`.
+    ///
+    /// `d` must not be the zero polynomial. Implemented as schoolbook long division
+    /// over GF(2): while the remainder's degree is at least `d`'s, XOR in `d` shifted
+    /// up so its leading term cancels the remainder's leading term, recording that
+    /// shift as a set bit of the quotient.
+    pub fn divmod(&self, d: &BinaryPolynomial) -> (BinaryPolynomial, BinaryPolynomial) {
+        let degree_d = d.degree();
+        assert!(degree_d >= 0, "cannot divide by the zero polynomial");
+
+        let mut remainder = self.clone();
+        let rn = remainder.coefficients.n;
+        let mut quotient = BinaryPolynomial::from_coefficients(&vec![0u8; rn]);
+        let qn = quotient.coefficients.n;
+
+        loop {
+            let degree_r = remainder.degree();
+            if degree_r < degree_d {
+                break;
+            }
+            let shift = (degree_r - degree_d) as usize;
+
+            quotient.coefficients.set(qn - 1 - shift, true);
+
+            for bit in d.coefficients.ones() {
+                let degree = (d.coefficients.n - 1 - bit) + shift;
+                remainder.coefficients.flip(rn - 1 - degree);
+            }
+        }
+
+        (quotient, remainder)
+    }
+
+    /// Compute the greatest common divisor of `self` and `other` via the Euclidean
+    /// algorithm, repeatedly taking remainders until one is zero.
+    pub fn gcd(&self, other: &BinaryPolynomial) -> BinaryPolynomial {
+        let mut a = self.clone();
+        let mut b = other.clone();
+        while b.degree() != -1 {
+            let (_, r) = a.divmod(&b);
+            a = b;
+            b = r;
+        }
+        a
+    }
+
+    /// Compute the inverse of `self` modulo `p` via the extended Euclidean algorithm,
+    /// or `None` if `self` and `p` are not coprime (i.e. `gcd(self, p) != 1`).
+    pub fn modinv(&self, p: &BinaryPolynomial) -> Option<BinaryPolynomial> {
+        // Track the cofactor of `self` at each step, so that at every point
+        // `s * self` reduces to the current remainder modulo `p`.
+        let mut r0 = p.clone();
+        let mut r1 = self.clone();
+        let mut s0 = BinaryPolynomial::from_coefficients(&[0]);
+        let mut s1 = BinaryPolynomial::from_coefficients(&[1]);
+
+        while r1.degree() != -1 {
+            let (q, r) = r0.divmod(&r1);
+            let s2 = poly_xor(&s0, &mul_plain(&q, &s1));
+            r0 = r1;
+            r1 = r;
+            s0 = s1;
+            s1 = s2;
+        }
+
+        if r0.degree() != 0 {
+            return None;
+        }
+
+        let (_, s) = s0.divmod(p);
+
+        // Re-pack the result at the same length as `p`, so it can be fed straight
+        // back into `modmult`/`modexp` alongside `self` and `p`.
+        let mut out = BinaryPolynomial::from_coefficients(&vec![0u8; p.coefficients.n]);
+        let outn = out.coefficients.n;
+        for bit in s.coefficients.ones() {
+            let degree = s.coefficients.n - 1 - bit;
+            out.coefficients.flip(outn - 1 - degree);
+        }
+        Some(out)
+    }
+
     /// Evaluate x^k mod self. k is interpreted as a large binary integer.
     pub fn modexp(&self, k: &BinaryVector) -> BinaryPolynomial {
         // Start at f=1. Need to construct f with same length as self.
         let offset = (64 - (self.coefficients.n % 64)) % 64;
-        let mut f = self.clone();
-        f.coefficients ^= &self.coefficients;
+        let mut f = BinaryPolynomial::from_coefficients(&vec![0u8; self.coefficients.n]);
         f.coefficients.data[self.coefficients.data.len()-1] = 1<<offset;
 
         if k.firstbit() == k.n {
@@ -169,13 +687,63 @@ impl BinaryPolynomial {
 
         // Construct the polynomial 1 with the same length as self.
         let offset = (64 - (self.coefficients.n % 64)) % 64;
-        let mut one = self.clone();
-        one.coefficients ^= &self.coefficients;
+        let mut one = BinaryPolynomial::from_coefficients(&vec![0u8; self.coefficients.n]);
         one.coefficients.data[self.coefficients.data.len()-1] = 1<<offset;
 
         return f.coefficients.data == one.coefficients.data;
     }
 
+    /// Checks if this degree-n polynomial is irreducible over GF(2), using the
+    /// Frobenius (Ben-Or) test: `x^(2^n) == x (mod self)`, and for every prime `q`
+    /// dividing `n`, `gcd(self, x^(2^(n/q)) - x) == 1`.
+    pub fn is_irreducible(&self) -> bool {
+        let degree = self.degree();
+
+        // The zero and unit polynomials aren't irreducible.
+        if degree <= 0 {
+            return false;
+        }
+
+        // Every degree-1 polynomial over GF(2) is irreducible.
+        let n = degree as usize;
+        if n == 1 {
+            return true;
+        }
+
+        // Construct x, at the same length as self, to repeatedly square mod self.
+        let offset = (64 - (self.coefficients.n % 64)) % 64;
+        let mut x = BinaryPolynomial::from_coefficients(&vec![0u8; self.coefficients.n]);
+        x.coefficients.data[self.coefficients.data.len()-1] = 2<<offset;
+
+        // x^(2^n) mod self, via n successive squarings.
+        let mut f = x.clone();
+        for _ in 0..n {
+            f = f.modmult(&f, self);
+        }
+
+        // f - x (XOR, since we're in characteristic 2).
+        f.coefficients ^= x.coefficients.clone();
+        if f.degree() != -1 {
+            return false;
+        }
+
+        for q in distinct_prime_factors(n) {
+            // x^(2^(n/q)) mod self
+            let mut g = x.clone();
+            for _ in 0..(n / q) {
+                g = g.modmult(&g, self);
+            }
+
+            // g - x (XOR), then check it shares no factor with self.
+            g.coefficients ^= x.coefficients.clone();
+            if self.gcd(&g).degree() != 0 {
+                return false;
+            }
+        }
+
+        true
+    }
+
     /// Evaluates if an irreducible polynomial is primitive.
     pub fn is_primitive(&self) -> bool {
         // No point checking 0-degree polynomials
@@ -183,19 +751,22 @@ impl BinaryPolynomial {
             return true;
         }
 
+        // Must actually be irreducible before primitivity is even meaningful.
+        if !self.is_irreducible() {
+            return false;
+        }
+
         // All primitive polynomials must have nonzero constant term
         if !self.coefficients[self.coefficients.n - 1] {
             return false;
         }
 
         // Must have an odd number of nonzero terms
-        if self.coefficients.count_ones() % 2 != 1 {
+        if self.coefficients.weight() % 2 != 1 {
             return false;
         }
 
-        println!("Checking primitivity of {}, degree is {}", self, self.degree());
         let factors = get_factors(self.degree() as usize);
-        println!("Got factors: {:?}", factors);
 
         // 2^k - 1 mod p must be 1 for k=degree(p)
         if !self.check_integer(&factors[0]) {
@@ -203,15 +774,33 @@ impl BinaryPolynomial {
         }
 
         for factor in &factors[1..] {
-            println!("    Testing factor {}", factor);
             if self.check_integer(&factor) {
-                println!("      Test failed, not primitive!");
                 return false;
             }
         }
-        println!("    All factors passed, primitive.");
         true
     }
+
+    /// Decompose this polynomial into square-free factors, each paired with its
+    /// multiplicity in `self`.
+    pub fn squarefree(&self) -> Vec<(BinaryPolynomial, usize)> {
+        squarefree_decompose(self)
+    }
+
+    /// Factor this polynomial into its irreducible factors (with repeats, so a factor
+    /// of multiplicity `m` appears `m` times) via square-free decomposition followed by
+    /// Berlekamp's algorithm on each square-free part.
+    pub fn factor(&self) -> Vec<BinaryPolynomial> {
+        let mut factors = Vec::new();
+        for (part, mult) in self.squarefree() {
+            for irreducible in factor_squarefree(&part) {
+                for _ in 0..mult {
+                    factors.push(irreducible.clone());
+                }
+            }
+        }
+        factors
+    }
 }
 
 #[cfg(test)]
@@ -264,6 +853,42 @@ mod tests {
         assert_eq!(p.eval(&y), 0);
     }
 
+    #[test]
+    fn test_mul() {
+        // x^2 * (x+1) = x^3 + x^2
+        let f = BinaryPolynomial::from_coefficients(&[1, 0, 0]);
+        let g = BinaryPolynomial::from_coefficients(&[1, 1]);
+        let fg = f.mul(&g);
+        assert_eq!(fg.coefficients.to_bits(), vec![1, 1, 0, 0]);
+
+        // (x+1) * (x+1) = x^2 + 1, the middle x terms cancel under GF(2) addition
+        let f = BinaryPolynomial::from_coefficients(&[1, 1]);
+        let ff = f.mul(&f);
+        assert_eq!(ff.coefficients.to_bits(), vec![1, 0, 1]);
+
+        // Multiplying by zero gives zero
+        let f = BinaryPolynomial::from_coefficients(&[1, 0, 1, 1]);
+        let zero = BinaryPolynomial::from_coefficients(&[0]);
+        assert_eq!(f.mul(&zero).coefficients.to_bits(), vec![0]);
+    }
+
+    #[test]
+    fn test_mul_reduce_mod_matches_modmult() {
+        // mul().reduce_mod() must agree with modmult() for every case modmult covers.
+        let p = BinaryPolynomial::from_coefficients(&[1, 0, 0, 0, 0, 0, 0]);
+        let f = BinaryPolynomial::from_coefficients(&[0, 0, 0, 0, 1, 0, 0]);
+        let g = BinaryPolynomial::from_coefficients(&[0, 0, 0, 0, 0, 1, 1]);
+        assert_eq!(format!("{}", f.mul(&g).reduce_mod(&p)), format!("{}", f.modmult(&g, &p)));
+
+        let p = BinaryPolynomial { coefficients: BinaryVector::from_words(256, &[
+            0x01000000_00000000, 0x00000000_00000000, 0x00000000_00000000, 0x00000000_00000000])};
+        let f = BinaryPolynomial { coefficients: BinaryVector::from_words(256, &[
+            0x00000000_00000000, 0x10000000_10000000, 0x10000000_10000000, 0x00000000_00000000])};
+        let g = BinaryPolynomial { coefficients: BinaryVector::from_words(256, &[
+            0x00000000_00000000, 0x00000000_00000000, 0x00000000_00000000, 0x00000000_00003010])};
+        assert_eq!(format!("{}", f.mul(&g).reduce_mod(&p)), format!("{}", f.modmult(&g, &p)));
+    }
+
     #[test]
     fn test_modmult() {
         // x^6 is suitably big to not have any effect
@@ -309,6 +934,66 @@ mod tests {
         assert_eq!(result.coefficients.data, fg.coefficients.data);
     }
 
+    #[test]
+    fn test_divmod() {
+        // x^3 + 1 = (x+1)(x^2+x+1), so this divides exactly.
+        let a = BinaryPolynomial::from_coefficients(&[1, 0, 0, 1]);
+        let b = BinaryPolynomial::from_coefficients(&[1, 1]);
+        let (q, r) = a.divmod(&b);
+        assert_eq!(q.coefficients.to_bits(), vec![0, 1, 1, 1]);
+        assert_eq!(r.coefficients.to_bits(), vec![0, 0, 0, 0]);
+
+        // x^3 + x + 1 divided by x^2 + x + 1 gives quotient x+1, remainder x.
+        let a = BinaryPolynomial::from_coefficients(&[1, 0, 1, 1]);
+        let b = BinaryPolynomial::from_coefficients(&[1, 1, 1]);
+        let (q, r) = a.divmod(&b);
+        assert_eq!(q.coefficients.to_bits(), vec![0, 0, 1, 1]);
+        assert_eq!(r.coefficients.to_bits(), vec![0, 0, 1, 0]);
+
+        // Dividing by a higher-degree polynomial gives a zero quotient.
+        let a = BinaryPolynomial::from_coefficients(&[1, 1]);
+        let b = BinaryPolynomial::from_coefficients(&[1, 0, 1]);
+        let (q, r) = a.divmod(&b);
+        assert_eq!(format!("{}", q), "0");
+        assert_eq!(format!("{}", r), "x + 1");
+    }
+
+    #[test]
+    fn test_gcd() {
+        // x^2 + 1 = (x+1)^2, so gcd(x^2+1, x+1) = x+1.
+        let a = BinaryPolynomial::from_coefficients(&[1, 0, 1]);
+        let b = BinaryPolynomial::from_coefficients(&[1, 1]);
+        assert_eq!(format!("{}", a.gcd(&b)), "x + 1");
+
+        // x^2 + x + 1 and x + 1 are coprime.
+        let a = BinaryPolynomial::from_coefficients(&[1, 1, 1]);
+        let b = BinaryPolynomial::from_coefficients(&[1, 1]);
+        assert_eq!(format!("{}", a.gcd(&b)), "1");
+
+        // gcd(f, 0) = f.
+        let a = BinaryPolynomial::from_coefficients(&[1, 1, 1]);
+        let zero = BinaryPolynomial::from_coefficients(&[0]);
+        assert_eq!(format!("{}", a.gcd(&zero)), "x^2 + x + 1");
+    }
+
+    #[test]
+    fn test_modinv() {
+        // x^4 + x^3 + 1 is primitive (see test_is_primitive), so every nonzero
+        // polynomial of lower degree is invertible modulo it.
+        let p = BinaryPolynomial::from_coefficients(&[1, 1, 0, 0, 1]);
+
+        // x, represented at the same length as p so the result can feed modmult directly.
+        let x = BinaryPolynomial::from_coefficients(&[0, 0, 0, 1, 0]);
+        let s = x.modinv(&p).expect("x should be invertible mod p");
+        assert_eq!(format!("{}", s), "x^3 + x^2");
+        assert_eq!(format!("{}", x.modmult(&s, &p)), "1");
+
+        // self and p share a factor (x+1), so they're not coprime.
+        let a = BinaryPolynomial::from_coefficients(&[1, 1]);
+        let q = BinaryPolynomial::from_coefficients(&[1, 0, 1]);
+        assert!(a.modinv(&q).is_none());
+    }
+
     #[test]
     fn test_modexp() {
         // First test a few values with degree(p)>k so mod doesn't come into it.
@@ -350,6 +1035,31 @@ mod tests {
         assert!(p.check_integer(&r));
     }
 
+    #[test]
+    fn test_is_irreducible() {
+        // Degree 0 and the zero polynomial are never irreducible.
+        assert!(!BinaryPolynomial::from_coefficients(&[1]).is_irreducible());
+        assert!(!BinaryPolynomial::from_coefficients(&[0]).is_irreducible());
+
+        // Every degree-1 polynomial is irreducible.
+        assert!(BinaryPolynomial::from_coefficients(&[1, 0]).is_irreducible());
+        assert!(BinaryPolynomial::from_coefficients(&[1, 1]).is_irreducible());
+
+        // x^2 + x + 1 is the only irreducible degree-2 polynomial.
+        assert!(BinaryPolynomial::from_coefficients(&[1, 1, 1]).is_irreducible());
+
+        // x^2 + 1 = (x+1)^2 is reducible.
+        assert!(!BinaryPolynomial::from_coefficients(&[1, 0, 1]).is_irreducible());
+
+        // x^4 + x^3 + 1 is irreducible (it's even primitive, see test_is_primitive).
+        let p = BinaryPolynomial::from_coefficients(&[1, 1, 0, 0, 1]);
+        assert!(p.is_irreducible());
+
+        // x^4 + x^2 + x + 1 is reducible (it has x+1 as a factor).
+        let p = BinaryPolynomial::from_coefficients(&[1, 0, 1, 1, 1]);
+        assert!(!p.is_irreducible());
+    }
+
     #[test]
     fn test_is_primitive() {
         // x^4 + x^3 + 1 is primitive
@@ -365,4 +1075,60 @@ mod tests {
             0x00000000_00000100, 0x00000000_00000000, 0x00000000_00000000, 0x00000000_0000002d])};
         assert!(p.is_primitive());
     }
+
+    #[test]
+    fn test_squarefree() {
+        // The zero and unit polynomials have no square-free factors.
+        assert_eq!(BinaryPolynomial::from_coefficients(&[0]).squarefree().len(), 0);
+        assert_eq!(BinaryPolynomial::from_coefficients(&[1]).squarefree().len(), 0);
+
+        // x^3 + 1 is already square-free.
+        let p = BinaryPolynomial::from_coefficients(&[1, 0, 0, 1]);
+        let sf = p.squarefree();
+        assert_eq!(sf.len(), 1);
+        assert_eq!(format!("{}", sf[0].0), "x^3 + 1");
+        assert_eq!(sf[0].1, 1);
+
+        // x^2 + 1 = (x+1)^2 is a perfect square.
+        let p = BinaryPolynomial::from_coefficients(&[1, 0, 1]);
+        let sf = p.squarefree();
+        assert_eq!(sf.len(), 1);
+        assert_eq!(format!("{}", sf[0].0), "x + 1");
+        assert_eq!(sf[0].1, 2);
+
+        // x^4 + x^3 + x + 1 = (x+1)^2 (x^2+x+1): mixed multiplicities.
+        let p = BinaryPolynomial::from_coefficients(&[1, 1, 0, 1, 1]);
+        let mut sf: Vec<(String, usize)> =
+            p.squarefree().iter().map(|t| (format!("{}", t.0), t.1)).collect();
+        sf.sort();
+        assert_eq!(sf, vec![
+            ("x + 1".to_owned(), 2), ("x^2 + x + 1".to_owned(), 1)]);
+    }
+
+    #[test]
+    fn test_factor() {
+        // An irreducible polynomial factors as itself.
+        let p = BinaryPolynomial::from_coefficients(&[1, 1, 1]);
+        let factors = p.factor();
+        assert_eq!(factors.len(), 1);
+        assert_eq!(format!("{}", factors[0]), "x^2 + x + 1");
+
+        // x^3 + 1 = (x+1)(x^2+x+1)
+        let p = BinaryPolynomial::from_coefficients(&[1, 0, 0, 1]);
+        let mut factors: Vec<String> = p.factor().iter().map(|f| format!("{}", f)).collect();
+        factors.sort();
+        assert_eq!(factors, vec!["x + 1".to_owned(), "x^2 + x + 1".to_owned()]);
+
+        // x^6 + x^4 + x + 1 = (x+1)(x^2+x+1)(x^3+x+1)
+        let p = BinaryPolynomial::from_coefficients(&[1, 0, 1, 0, 0, 1, 1]);
+        let factors = p.factor();
+        assert_eq!(factors.len(), 3);
+        for factor in &factors {
+            assert!(factor.is_irreducible());
+        }
+        let mut strs: Vec<String> = factors.iter().map(|f| format!("{}", f)).collect();
+        strs.sort();
+        assert_eq!(strs, vec![
+            "x + 1".to_owned(), "x^2 + x + 1".to_owned(), "x^3 + x + 1".to_owned()]);
+    }
 }
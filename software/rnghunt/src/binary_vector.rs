@@ -1,30 +1,114 @@
 // Copyright 2017 Adam Greig
 
-use std::cmp::{PartialEq, Eq};
-use std::ops::{Index, Range};
-use std::ops::{BitXor, BitXorAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign};
-use std::ops::{Shl, ShlAssign, Shr, ShrAssign};
-use std::fmt;
+use core::cmp::{PartialEq, Eq, PartialOrd, Ord, Ordering};
+use core::hash::{Hash, Hasher};
+use core::ops::{Index, Range};
+use core::ops::{BitXor, BitXorAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign};
+use core::ops::{Shl, ShlAssign, Shr, ShrAssign};
+use core::fmt;
+use core::iter;
 
-use ::numwords;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::borrow::ToOwned;
 
-/// A binary matrix of length (n).
+use ::bitblock::BitBlock;
+
+/// The number of `B` words required to store `n` bits.
+#[inline(always)]
+fn blockwords<B: BitBlock>(n: usize) -> usize {
+    (n + (B::BITS as usize) - 1) / (B::BITS as usize)
+}
+
+/// A binary vector of length (n), generic over the packed word type `B`.
+///
+/// See [`BitBlock`] for the requirements on a word type; use a smaller word, such as
+/// `u32`, on targets where `u64` operations are inefficient or unavailable. Most code
+/// should use the [`BinaryVector`] alias below, which fixes `B` to `u64`.
 #[derive(Clone,Debug)]
-pub struct BinaryVector {
+pub struct BinaryVectorBlock<B: BitBlock> {
     pub n: usize,
 
-    /// Bits stored packed into u64 words, MSbit first. If `n` is not a multiple of 64,
-    /// the final (least significant) bits of the final word are ignored.
-    pub data: Vec<u64>,
+    /// Bits stored packed into `B` words, MSbit first. If `n` is not a multiple of
+    /// `B::BITS`, the final (least significant) bits of the final word are ignored.
+    pub data: Vec<B>,
+}
+
+/// A binary vector of length (n), packed into `u64` words.
+pub type BinaryVector = BinaryVectorBlock<u64>;
+
+/// Errors that can occur while decoding a framed BinaryVector with `BinaryVector::decode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The buffer ended before the declared length's payload was fully present.
+    Truncated,
+    /// The unused pad bits in the final payload byte were not all zero.
+    InvalidPadding,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DecodeError::Truncated => write!(f, "buffer truncated before declared length"),
+            DecodeError::InvalidPadding => write!(f, "non-zero padding bits in final byte"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for DecodeError {
+    fn description(&self) -> &str {
+        match *self {
+            DecodeError::Truncated => "buffer truncated before declared length",
+            DecodeError::InvalidPadding => "non-zero padding bits in final byte",
+        }
+    }
 }
 
-impl Index<usize> for BinaryVector {
+/// Write `v` to `out` as a little-endian base-128 varint (7 data bits per byte, MSbit
+/// of each byte set while more bytes follow).
+fn write_varint(mut v: usize, out: &mut Vec<u8>) {
+    loop {
+        let byte = (v & 0x7f) as u8;
+        v >>= 7;
+        if v != 0 {
+            out.push(byte | 0x80);
+        } else {
+            out.push(byte);
+            break;
+        }
+    }
+}
+
+/// Read a varint written by `write_varint` from the front of `bytes`, returning the
+/// decoded value and the remaining bytes.
+fn read_varint(bytes: &[u8]) -> Result<(usize, &[u8]), DecodeError> {
+    let mut v = 0usize;
+    let mut shift = 0u32;
+    for (idx, &byte) in bytes.iter().enumerate() {
+        v |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((v, &bytes[idx+1..]));
+        }
+        shift += 7;
+    }
+    Err(DecodeError::Truncated)
+}
+
+impl<B: BitBlock> Index<usize> for BinaryVectorBlock<B> {
     type Output = bool;
 
     /// Fetch a specific bit in this vector
     fn index(&self, i: usize) -> &bool {
         assert!(i < self.n);
-        if self.data[i/64] >> (63-(i%64)) & 1 == 1 {
+        let bits = B::BITS as usize;
+        let shift = (bits - 1 - (i % bits)) as u32;
+        if self.data[i/bits] >> shift & B::ONE == B::ONE {
             &true
         } else {
             &false
@@ -32,111 +116,115 @@ impl Index<usize> for BinaryVector {
     }
 }
 
-impl fmt::Display for BinaryVector {
+#[cfg(feature = "std")]
+impl<B: BitBlock> fmt::Display for BinaryVectorBlock<B> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.to_bitstring())
     }
 }
 
-impl<'a> BitAnd for &'a BinaryVector {
-    type Output = BinaryVector;
-    fn bitand(self, rhs: &BinaryVector) -> BinaryVector {
+impl<'a, B: BitBlock> BitAnd for &'a BinaryVectorBlock<B> {
+    type Output = BinaryVectorBlock<B>;
+    fn bitand(self, rhs: &BinaryVectorBlock<B>) -> BinaryVectorBlock<B> {
         assert_eq!(self.n, rhs.n);
         let mut data = self.data.clone();
-        for wordidx in 0..numwords(self.n) {
+        for wordidx in 0..blockwords::<B>(self.n) {
             data[wordidx] &= rhs.data[wordidx];
         }
-        BinaryVector { n: self.n, data: data }
+        BinaryVectorBlock { n: self.n, data: data }
     }
 }
 
-impl BitAndAssign for BinaryVector {
+impl<B: BitBlock> BitAndAssign for BinaryVectorBlock<B> {
     fn bitand_assign(&mut self, rhs: Self) {
         assert_eq!(self.n, rhs.n);
-        for wordidx in 0..numwords(self.n) {
+        for wordidx in 0..blockwords::<B>(self.n) {
             self.data[wordidx] &= rhs.data[wordidx];
         }
     }
 }
 
-impl<'a> BitOr for &'a BinaryVector {
-    type Output = BinaryVector;
-    fn bitor(self, rhs: &BinaryVector) -> BinaryVector {
+impl<'a, B: BitBlock> BitOr for &'a BinaryVectorBlock<B> {
+    type Output = BinaryVectorBlock<B>;
+    fn bitor(self, rhs: &BinaryVectorBlock<B>) -> BinaryVectorBlock<B> {
         assert_eq!(self.n, rhs.n);
         let mut data = self.data.clone();
-        for wordidx in 0..numwords(self.n) {
+        for wordidx in 0..blockwords::<B>(self.n) {
             data[wordidx] |= rhs.data[wordidx];
         }
-        BinaryVector { n: self.n, data: data }
+        BinaryVectorBlock { n: self.n, data: data }
     }
 }
 
-impl BitOrAssign for BinaryVector {
+impl<B: BitBlock> BitOrAssign for BinaryVectorBlock<B> {
     fn bitor_assign(&mut self, rhs: Self) {
         assert_eq!(self.n, rhs.n);
-        for wordidx in 0..numwords(self.n) {
+        for wordidx in 0..blockwords::<B>(self.n) {
             self.data[wordidx] |= rhs.data[wordidx];
         }
     }
 }
 
-impl<'a> BitXor for &'a BinaryVector {
-    type Output = BinaryVector;
-    fn bitxor(self, rhs: &BinaryVector) -> BinaryVector {
+impl<'a, B: BitBlock> BitXor for &'a BinaryVectorBlock<B> {
+    type Output = BinaryVectorBlock<B>;
+    fn bitxor(self, rhs: &BinaryVectorBlock<B>) -> BinaryVectorBlock<B> {
         assert_eq!(self.n, rhs.n);
         let mut data = self.data.clone();
-        for wordidx in 0..numwords(self.n) {
+        for wordidx in 0..blockwords::<B>(self.n) {
             data[wordidx] ^= rhs.data[wordidx];
         }
-        BinaryVector { n: self.n, data: data }
+        BinaryVectorBlock { n: self.n, data: data }
     }
 }
 
-impl BitXorAssign for BinaryVector {
+impl<B: BitBlock> BitXorAssign for BinaryVectorBlock<B> {
     fn bitxor_assign(&mut self, rhs: Self) {
         assert_eq!(self.n, rhs.n);
-        for wordidx in 0..numwords(self.n) {
+        for wordidx in 0..blockwords::<B>(self.n) {
             self.data[wordidx] ^= rhs.data[wordidx];
         }
     }
 }
 
-impl<'a> Shl<usize> for &'a BinaryVector {
-    type Output = BinaryVector;
-    fn shl(self, i: usize) -> BinaryVector {
+impl<'a, B: BitBlock> Shl<usize> for &'a BinaryVectorBlock<B> {
+    type Output = BinaryVectorBlock<B>;
+    fn shl(self, i: usize) -> BinaryVectorBlock<B> {
         assert!(i <= self.n);
+        let bits = B::BITS as usize;
         let n = self.n - i;
-        let nwords = numwords(n);
-        let offset = i % 64;
+        let nwords = blockwords::<B>(n);
+        let offset = i % bits;
         if offset == 0 {
-            BinaryVector { n: n, data: self.data[i/64..].to_owned() }
+            BinaryVectorBlock { n: n, data: self.data[i/bits..].to_owned() }
         } else {
-            let mut data = vec![0u64; nwords];
+            let mut data = vec![B::ZERO; nwords];
             for idx in 0..nwords {
-                let wordidx = (i+idx*64)/64;
-                data[idx] = self.data[wordidx] << offset;
-                if idx != (nwords - 1) || wordidx != (self.n-1)/64 {
-                    data[idx] |= self.data[wordidx+1] >> (64-offset);
+                let wordidx = (i+idx*bits)/bits;
+                data[idx] = self.data[wordidx] << (offset as u32);
+                if idx != (nwords - 1) || wordidx != (self.n-1)/bits {
+                    data[idx] |= self.data[wordidx+1] >> ((bits-offset) as u32);
                 }
             }
-            BinaryVector { n: n, data: data }
+            BinaryVectorBlock { n: n, data: data }
         }
     }
 }
 
-impl ShlAssign<usize> for BinaryVector {
+impl<B: BitBlock> ShlAssign<usize> for BinaryVectorBlock<B> {
     fn shl_assign(&mut self, i: usize) {
         assert!(i <= self.n);
+        let bits = B::BITS as usize;
         self.n -= i;
-        let offset = i % 64;
+        let offset = i % bits;
         if offset == 0 {
-            self.data = self.data[i/64..].to_owned();
+            self.data = self.data[i/bits..].to_owned();
         } else {
-            let nwords = numwords(self.n + i);
+            let nwords = blockwords::<B>(self.n + i);
             for idx in 0..nwords {
-                self.data[idx] <<= offset;
+                self.data[idx] = self.data[idx] << (offset as u32);
                 if idx != nwords-1 {
-                    self.data[idx] |= self.data[idx+1] >> (64-offset);
+                    let carry = self.data[idx+1] >> ((bits-offset) as u32);
+                    self.data[idx] |= carry;
                 }
             }
             self.data.truncate(nwords);
@@ -144,63 +232,67 @@ impl ShlAssign<usize> for BinaryVector {
     }
 }
 
-impl ShrAssign<usize> for BinaryVector {
+impl<B: BitBlock> ShrAssign<usize> for BinaryVectorBlock<B> {
     fn shr_assign(&mut self, i: usize) {
+        let bits = B::BITS as usize;
         self.n += i;
-        let nwords = numwords(self.n);
-        let offset = i % 64;
-        let mut data = vec![0u64; i/64];
+        let nwords = blockwords::<B>(self.n);
+        let offset = i % bits;
+        let mut data = vec![B::ZERO; i/bits];
         data.extend_from_slice(&self.data);
         if nwords > data.len() {
-            data.push(0u64);
+            data.push(B::ZERO);
         }
         self.data = data;
         if offset != 0 {
-            for idx in (i/64..nwords).rev() {
-                self.data[idx] >>= offset;
+            for idx in (i/bits..nwords).rev() {
+                self.data[idx] = self.data[idx] >> (offset as u32);
                 if idx != 0 {
-                    self.data[idx] |= self.data[idx-1] << (64-offset);
+                    let carry = self.data[idx-1] << ((bits-offset) as u32);
+                    self.data[idx] |= carry;
                 }
             }
         }
     }
 }
 
-impl<'a> Shr<usize> for &'a BinaryVector {
-    type Output = BinaryVector;
-    fn shr(self, i: usize) -> BinaryVector {
+impl<'a, B: BitBlock> Shr<usize> for &'a BinaryVectorBlock<B> {
+    type Output = BinaryVectorBlock<B>;
+    fn shr(self, i: usize) -> BinaryVectorBlock<B> {
+        let bits = B::BITS as usize;
         let n = self.n + i;
-        let nwords = numwords(n);
-        let offset = i % 64;
+        let nwords = blockwords::<B>(n);
+        let offset = i % bits;
         if offset == 0 {
-            let mut data = vec![0u64; i/64];
+            let mut data = vec![B::ZERO; i/bits];
             data.extend_from_slice(&self.data);
-            BinaryVector { n: n, data: data }
+            BinaryVectorBlock { n: n, data: data }
         } else {
-            let mut data = vec![0u64; nwords];
-            for (srcidx, dstidx) in ((i/64)..nwords).enumerate() {
+            let mut data = vec![B::ZERO; nwords];
+            for (srcidx, dstidx) in ((i/bits)..nwords).enumerate() {
                 if srcidx > 0 {
-                    data[dstidx] = self.data[srcidx-1] << (64-offset);
+                    data[dstidx] = self.data[srcidx-1] << ((bits-offset) as u32);
                 }
-                if dstidx != nwords-1 || srcidx < numwords(self.n) {
-                    data[dstidx] |= self.data[srcidx] >> offset;
+                if dstidx != nwords-1 || srcidx < blockwords::<B>(self.n) {
+                    data[dstidx] |= self.data[srcidx] >> (offset as u32);
                 }
             }
-            BinaryVector { n: n, data: data }
+            BinaryVectorBlock { n: n, data: data }
         }
     }
 }
 
-impl PartialEq for BinaryVector {
-    fn eq(&self, other: &BinaryVector) -> bool {
+impl<B: BitBlock> PartialEq for BinaryVectorBlock<B> {
+    fn eq(&self, other: &BinaryVectorBlock<B>) -> bool {
         if self.n == other.n {
+            let bits = B::BITS as usize;
             let mut eq: bool = true;
-            let nwords = numwords(self.n);
+            let nwords = blockwords::<B>(self.n);
             for wordidx in 0..(nwords-1) {
                 eq &= self.data[wordidx] == other.data[wordidx];
             }
-            if self.n % 64 != 0 {
-                let mask = 0xFFFF_FFFF_FFFF_FFFF >> (64 - (self.n % 64));
+            if self.n % bits != 0 {
+                let mask = B::ONES << ((bits - (self.n % bits)) as u32);
                 eq &= (self.data[nwords-1] & mask) == (other.data[nwords-1] & mask);
             } else {
                 eq &= self.data[nwords-1] == other.data[nwords-1];
@@ -212,34 +304,84 @@ impl PartialEq for BinaryVector {
     }
 }
 
-impl Eq for BinaryVector {}
+impl<B: BitBlock> Eq for BinaryVectorBlock<B> {}
+
+impl<B: BitBlock> PartialOrd for BinaryVectorBlock<B> {
+    fn partial_cmp(&self, other: &BinaryVectorBlock<B>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Ordered lexicographically by bit content (MSbit first) over the common prefix of
+/// the two vectors, with the shorter vector ordered first if one is a prefix of
+/// the other.
+impl<B: BitBlock> Ord for BinaryVectorBlock<B> {
+    fn cmp(&self, other: &BinaryVectorBlock<B>) -> Ordering {
+        let bits = B::BITS as usize;
+        let common = if self.n < other.n { self.n } else { other.n };
+        let commonwords = blockwords::<B>(common);
+        for wordidx in 0..commonwords {
+            let mut a = self.data[wordidx];
+            let mut b = other.data[wordidx];
+            if wordidx == commonwords - 1 && common % bits != 0 {
+                let mask = B::ONES << ((bits - (common % bits)) as u32);
+                a = a & mask;
+                b = b & mask;
+            }
+            match a.cmp(&b) {
+                Ordering::Equal => continue,
+                ord => return ord,
+            }
+        }
+        self.n.cmp(&other.n)
+    }
+}
 
-impl BinaryVector {
-    /// Make a new BinaryVector from the given words (packed)
+/// Hashes consistently with `Eq`: the length plus the masked words, so two vectors
+/// that compare equal (including ignoring any differing tail padding) hash equally.
+impl<B: BitBlock> Hash for BinaryVectorBlock<B> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let bits = B::BITS as usize;
+        let nwords = blockwords::<B>(self.n);
+        self.n.hash(state);
+        for wordidx in 0..nwords {
+            let mut word = self.data[wordidx];
+            if wordidx == nwords - 1 && self.n % bits != 0 {
+                word = word & (B::ONES << ((bits - (self.n % bits)) as u32));
+            }
+            word.hash(state);
+        }
+    }
+}
+
+impl<B: BitBlock> BinaryVectorBlock<B> {
+    /// Make a new BinaryVectorBlock from the given words (packed)
     ///
     /// `words` contains the packed bits, MSbit first, with the excess bits ignored.
-    pub fn from_words(n: usize, words: &[u64]) -> BinaryVector {
-        BinaryVector { n: n, data: words.to_owned() }
+    pub fn from_words(n: usize, words: &[B]) -> BinaryVectorBlock<B> {
+        BinaryVectorBlock { n: n, data: words.to_owned() }
     }
 
-    /// Make a new BinaryVector from the given bits (unpacked)
+    /// Make a new BinaryVectorBlock from the given bits (unpacked)
     ///
     /// `bits` contains just 0 and 1 entries, and the length of the vector is set to the
     /// length of this slice.
-    pub fn from_bits(bits: &[u8]) -> BinaryVector {
+    pub fn from_bits(bits: &[u8]) -> BinaryVectorBlock<B> {
+        let blockbits = B::BITS as usize;
         let n = bits.len();
-        let mut data = vec![0u64; numwords(n)];
+        let mut data = vec![B::ZERO; blockwords::<B>(n)];
         for (idx, bit) in bits.iter().enumerate() {
             assert!(*bit == 0 || *bit == 1);
             if *bit == 1 {
-                data[idx/64] |= 1<<(63-(idx%64));
+                data[idx/blockbits] |= B::ONE << ((blockbits-1-(idx%blockbits)) as u32);
             }
         }
-        BinaryVector { n: n, data: data }
+        BinaryVectorBlock { n: n, data: data }
     }
 
-    /// Make a new BinaryVector from a bitstring
-    pub fn from_bitstring(bitstring: &str) -> BinaryVector {
+    /// Make a new BinaryVectorBlock from a bitstring
+    #[cfg(feature = "std")]
+    pub fn from_bitstring(bitstring: &str) -> BinaryVectorBlock<B> {
         let mut bits = Vec::with_capacity(bitstring.len());
         for c in bitstring.chars() {
             assert!(c == '0' || c == '1');
@@ -249,7 +391,7 @@ impl BinaryVector {
                 bits.push(1);
             }
         }
-        BinaryVector::from_bits(&bits)
+        BinaryVectorBlock::from_bits(&bits)
     }
 
     /// Convert to unpacked bits
@@ -264,6 +406,7 @@ impl BinaryVector {
     }
 
     /// Convert to a String of 0/1
+    #[cfg(feature = "std")]
     pub fn to_bitstring(&self) -> String {
         let mut s = String::with_capacity(self.n);
         for bit in self.to_bits() {
@@ -276,30 +419,240 @@ impl BinaryVector {
         s
     }
 
-    /// Make a new BinaryVector from a range into the current one
-    pub fn slice(&self, range: Range<usize>) -> BinaryVector {
+    /// Pack into the minimum number of bytes (`ceil(n/8)`), MSbit first, zero-padding
+    /// any unused bits at the end of the final byte.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let nbytes = (self.n + 7) / 8;
+        let mut out = vec![0u8; nbytes];
+        for i in 0..self.n {
+            if self[i] {
+                out[i/8] |= 1 << (7 - (i % 8));
+            }
+        }
+        out
+    }
+
+    /// Unpack `n` bits from the minimal byte packing produced by `to_bytes`.
+    pub fn from_bytes(n: usize, bytes: &[u8]) -> BinaryVectorBlock<B> {
+        assert_eq!(bytes.len(), (n + 7) / 8);
+        let blockbits = B::BITS as usize;
+        let mut data = vec![B::ZERO; blockwords::<B>(n)];
+        for i in 0..n {
+            if bytes[i/8] >> (7 - (i % 8)) & 1 == 1 {
+                data[i/blockbits] |= B::ONE << ((blockbits-1-(i%blockbits)) as u32);
+            }
+        }
+        BinaryVectorBlock { n: n, data: data }
+    }
+
+    /// Encode as a self-describing frame: a varint-encoded length header followed by
+    /// the packed payload from `to_bytes`. Frames can be concatenated and parsed back
+    /// one at a time with `decode`.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_varint(self.n, &mut out);
+        out.extend_from_slice(&self.to_bytes());
+        out
+    }
+
+    /// Decode a single frame produced by `encode` from the front of `bytes`, returning
+    /// the decoded vector and the remaining, unconsumed bytes.
+    ///
+    /// Returns an error rather than panicking if `bytes` doesn't hold enough data for
+    /// the declared length, or if the final byte's pad bits aren't all zero.
+    pub fn decode(bytes: &[u8]) -> Result<(BinaryVectorBlock<B>, &[u8]), DecodeError> {
+        let (n, rest) = read_varint(bytes)?;
+        let nbytes = (n + 7) / 8;
+        if rest.len() < nbytes {
+            return Err(DecodeError::Truncated);
+        }
+        let (payload, rest) = rest.split_at(nbytes);
+        if n % 8 != 0 {
+            let padmask = 0xFFu8 >> (n % 8);
+            if payload[nbytes - 1] & padmask != 0 {
+                return Err(DecodeError::InvalidPadding);
+            }
+        }
+        Ok((BinaryVectorBlock::from_bytes(n, payload), rest))
+    }
+
+    /// Fetch a specific bit in this vector.
+    ///
+    /// Equivalent to `self[i]`, provided as a named method for use in generic code.
+    pub fn get(&self, i: usize) -> bool {
+        self[i]
+    }
+
+    /// Set a specific bit in this vector to `val`, returning whether it changed.
+    pub fn set(&mut self, i: usize, val: bool) -> bool {
+        assert!(i < self.n);
+        let bits = B::BITS as usize;
+        let mask = B::ONE << ((bits - 1 - (i % bits)) as u32);
+        let changed = (self.data[i/bits] & mask != B::ZERO) != val;
+        if val {
+            self.data[i/bits] |= mask;
+        } else {
+            self.data[i/bits] &= !mask;
+        }
+        changed
+    }
+
+    /// Clear a specific bit in this vector, returning whether it changed.
+    pub fn clear(&mut self, i: usize) -> bool {
+        self.set(i, false)
+    }
+
+    /// Flip a specific bit in this vector.
+    pub fn flip(&mut self, i: usize) {
+        assert!(i < self.n);
+        let bits = B::BITS as usize;
+        self.data[i/bits] ^= B::ONE << ((bits - 1 - (i % bits)) as u32);
+    }
+
+    /// Set every bit in this vector.
+    pub fn set_all(&mut self) {
+        let bits = B::BITS as usize;
+        let nwords = blockwords::<B>(self.n);
+        for wordidx in 0..nwords {
+            self.data[wordidx] = B::ONES;
+        }
+        if self.n % bits != 0 {
+            self.data[nwords - 1] &= B::ONES << ((bits - (self.n % bits)) as u32);
+        }
+    }
+
+    /// Clear every bit in this vector.
+    pub fn clear_all(&mut self) {
+        let nwords = blockwords::<B>(self.n);
+        for wordidx in 0..nwords {
+            self.data[wordidx] = B::ZERO;
+        }
+    }
+
+    /// Bitwise AND with `other`, treating whichever vector is shorter as zero-extended
+    /// up to the length of the longer one, rather than requiring equal lengths.
+    pub fn bitand_padded(&self, other: &BinaryVectorBlock<B>) -> BinaryVectorBlock<B> {
+        let n = if self.n > other.n { self.n } else { other.n };
+        let mut data = vec![B::ZERO; blockwords::<B>(n)];
+        for wordidx in 0..blockwords::<B>(n) {
+            let a = self.data.get(wordidx).cloned().unwrap_or(B::ZERO);
+            let b = other.data.get(wordidx).cloned().unwrap_or(B::ZERO);
+            data[wordidx] = a & b;
+        }
+        BinaryVectorBlock { n: n, data: data }
+    }
+
+    /// Bitwise OR with `other`, treating whichever vector is shorter as zero-extended
+    /// up to the length of the longer one, rather than requiring equal lengths.
+    pub fn bitor_padded(&self, other: &BinaryVectorBlock<B>) -> BinaryVectorBlock<B> {
+        let n = if self.n > other.n { self.n } else { other.n };
+        let mut data = vec![B::ZERO; blockwords::<B>(n)];
+        for wordidx in 0..blockwords::<B>(n) {
+            let a = self.data.get(wordidx).cloned().unwrap_or(B::ZERO);
+            let b = other.data.get(wordidx).cloned().unwrap_or(B::ZERO);
+            data[wordidx] = a | b;
+        }
+        BinaryVectorBlock { n: n, data: data }
+    }
+
+    /// Bitwise XOR with `other`, treating whichever vector is shorter as zero-extended
+    /// up to the length of the longer one, rather than requiring equal lengths.
+    pub fn bitxor_padded(&self, other: &BinaryVectorBlock<B>) -> BinaryVectorBlock<B> {
+        let n = if self.n > other.n { self.n } else { other.n };
+        let mut data = vec![B::ZERO; blockwords::<B>(n)];
+        for wordidx in 0..blockwords::<B>(n) {
+            let a = self.data.get(wordidx).cloned().unwrap_or(B::ZERO);
+            let b = other.data.get(wordidx).cloned().unwrap_or(B::ZERO);
+            data[wordidx] = a ^ b;
+        }
+        BinaryVectorBlock { n: n, data: data }
+    }
+
+    /// Compute the Hamming weight of this vector, i.e. the number of set bits.
+    pub fn weight(&self) -> usize {
+        let bits = B::BITS as usize;
+        let nwords = blockwords::<B>(self.n);
+        let mut weight = 0usize;
+        for wordidx in 0..nwords {
+            let mut word = self.data[wordidx];
+            if wordidx == nwords - 1 && self.n % bits != 0 {
+                word = word & (B::ONES << ((bits - (self.n % bits)) as u32));
+            }
+            weight += word.count_ones() as usize;
+        }
+        weight
+    }
+
+    /// Compute the Hamming distance to `other`, i.e. the number of bits that differ.
+    ///
+    /// Both vectors must have the same length.
+    pub fn hamming_distance(&self, other: &BinaryVectorBlock<B>) -> usize {
+        assert_eq!(self.n, other.n);
+        (self ^ other).weight()
+    }
+
+    /// Iterate over the indices of the set bits in this vector, in ascending order.
+    pub fn ones<'a>(&'a self) -> impl Iterator<Item=usize> + 'a {
+        let bits = B::BITS as usize;
+        let nwords = blockwords::<B>(self.n);
+        let tailmask = if self.n % bits != 0 {
+            B::ONES << ((bits - (self.n % bits)) as u32)
+        } else {
+            B::ONES
+        };
+        let mut wordidx = 0usize;
+        let mut word = if nwords > 0 {
+            if nwords == 1 { self.data[0] & tailmask } else { self.data[0] }
+        } else {
+            B::ZERO
+        };
+        iter::from_fn(move || {
+            while word == B::ZERO {
+                wordidx += 1;
+                if wordidx >= nwords {
+                    return None;
+                }
+                word = self.data[wordidx];
+                if wordidx == nwords - 1 {
+                    word = word & tailmask;
+                }
+            }
+            let bit = word.leading_zeros() as usize;
+            word = word & !(B::ONE << ((bits - 1 - bit) as u32));
+            Some(wordidx * bits + bit)
+        })
+    }
+
+    /// Index of the first (most significant) set bit, or `n` if no bits are set.
+    pub fn firstbit(&self) -> usize {
+        self.ones().next().unwrap_or(self.n)
+    }
+
+    /// Make a new BinaryVectorBlock from a range into the current one
+    pub fn slice(&self, range: Range<usize>) -> BinaryVectorBlock<B> {
+        let bits = B::BITS as usize;
         let start = range.start;
         let end = range.end;
         assert!(end >= start);
         assert!(end <= self.n);
         let n = end - start;
-        let nwords = numwords(n);
-        let offset = start % 64;
+        let nwords = blockwords::<B>(n);
+        let offset = start % bits;
 
         if offset == 0 {
             // Short circuit the case where start is aligned to whole words
-            let data = self.data[start/64..(end+63)/64].to_owned();
-            BinaryVector { n: n, data: data }
+            let data = self.data[start/bits..(end+bits-1)/bits].to_owned();
+            BinaryVectorBlock { n: n, data: data }
         } else {
-            let mut data = vec![0u64; nwords];
+            let mut data = vec![B::ZERO; nwords];
             for idx in 0..nwords {
-                let wordidx = (start+idx*64)/64;
-                data[idx] = self.data[wordidx] << offset;
-                if idx != (nwords - 1) || wordidx != (end-1)/64 {
-                    data[idx] |= self.data[wordidx+1] >> (64 - offset);
+                let wordidx = (start+idx*bits)/bits;
+                data[idx] = self.data[wordidx] << (offset as u32);
+                if idx != (nwords - 1) || wordidx != (end-1)/bits {
+                    data[idx] |= self.data[wordidx+1] >> ((bits - offset) as u32);
                 }
             }
-            BinaryVector { n: n, data: data }
+            BinaryVectorBlock { n: n, data: data }
         }
     }
 }
@@ -307,6 +660,9 @@ impl BinaryVector {
 #[cfg(test)]
 mod tests {
     use ::BinaryVector;
+    use super::DecodeError;
+    use std::collections::{BTreeSet, HashSet};
+    use std::cmp::Ordering;
 
     #[test]
     fn test_index() {
@@ -321,6 +677,148 @@ mod tests {
         assert_eq!(x[7], true);
     }
 
+    #[test]
+    fn test_to_bytes_from_bytes() {
+        let x = BinaryVector { n: 8, data: vec![0x8300_0000_0000_0000] };
+        assert_eq!(x.to_bytes(), vec![0x83]);
+        assert_eq!(BinaryVector::from_bytes(8, &[0x83]), x);
+
+        // Non-byte-aligned length, final byte zero-padded
+        let x = BinaryVector::from_bits(&[1, 0, 1, 1, 0]);
+        assert_eq!(x.to_bytes(), vec![0b1011_0000]);
+        assert_eq!(BinaryVector::from_bytes(5, &[0b1011_0000]), x);
+
+        let x = BinaryVector::from_words(96, &[0x883C385E66BD8704, 0xE43A5DF300000000]);
+        assert_eq!(BinaryVector::from_bytes(96, &x.to_bytes()), x);
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let x = BinaryVector::from_bits(&[1, 0, 1, 1, 0]);
+        let y = BinaryVector::from_words(96, &[0x883C385E66BD8704, 0xE43A5DF300000000]);
+
+        // Round-trip a single vector
+        let encoded = x.encode();
+        let (decoded, rest) = BinaryVector::decode(&encoded).unwrap();
+        assert_eq!(decoded, x);
+        assert!(rest.is_empty());
+
+        // Multiple frames can be streamed back to back
+        let mut buf = x.encode();
+        buf.extend_from_slice(&y.encode());
+        let (first, rest) = BinaryVector::decode(&buf).unwrap();
+        assert_eq!(first, x);
+        let (second, rest) = BinaryVector::decode(rest).unwrap();
+        assert_eq!(second, y);
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let x = BinaryVector::from_bits(&[1, 0, 1, 1, 0]);
+        let mut encoded = x.encode();
+
+        // Truncating the payload is an error, not a panic
+        let len = encoded.len();
+        encoded.truncate(len - 1);
+        assert_eq!(BinaryVector::decode(&encoded), Err(DecodeError::Truncated));
+
+        // Non-zero pad bits in the final byte are an error
+        let mut encoded = x.encode();
+        let last = encoded.len() - 1;
+        encoded[last] |= 1;
+        assert_eq!(BinaryVector::decode(&encoded), Err(DecodeError::InvalidPadding));
+    }
+
+    #[test]
+    fn test_get_set_clear_flip() {
+        let mut x = BinaryVector { n: 8, data: vec![0x0000_0000_0000_0000] };
+        assert_eq!(x.get(0), false);
+        assert_eq!(x.set(0, true), true);
+        assert_eq!(x.get(0), true);
+        assert_eq!(x.set(0, true), false);
+        assert_eq!(x.clear(0), true);
+        assert_eq!(x.get(0), false);
+        assert_eq!(x.clear(0), false);
+
+        x.flip(3);
+        assert_eq!(x.get(3), true);
+        x.flip(3);
+        assert_eq!(x.get(3), false);
+    }
+
+    #[test]
+    fn test_set_all_clear_all() {
+        let mut x = BinaryVector { n: 4, data: vec![0u64] };
+        x.set_all();
+        assert_eq!(x.data, vec![0xF000_0000_0000_0000]);
+        x.clear_all();
+        assert_eq!(x.data, vec![0u64]);
+    }
+
+    #[test]
+    fn test_bitand_padded() {
+        let x = BinaryVector::from_words(96, &[0x883C385E66BD8704, 0xE43A5DF300000000]);
+        let y = BinaryVector::from_words(64, &[0xA8F3B1900CC10FFF]);
+        let z = BinaryVector::from_words(96, &[0x883C385E66BD8704 & 0xA8F3B1900CC10FFF, 0]);
+        assert_eq!(x.bitand_padded(&y), z);
+        assert_eq!(y.bitand_padded(&x), z);
+    }
+
+    #[test]
+    fn test_bitor_padded() {
+        let x = BinaryVector::from_words(96, &[0x883C385E66BD8704, 0xE43A5DF300000000]);
+        let y = BinaryVector::from_words(64, &[0xA8F3B1900CC10FFF]);
+        let z = BinaryVector::from_words(96, &[0x883C385E66BD8704 | 0xA8F3B1900CC10FFF,
+                                               0xE43A5DF300000000]);
+        assert_eq!(x.bitor_padded(&y), z);
+        assert_eq!(y.bitor_padded(&x), z);
+    }
+
+    #[test]
+    fn test_bitxor_padded() {
+        let x = BinaryVector::from_words(96, &[0x883C385E66BD8704, 0xE43A5DF300000000]);
+        let y = BinaryVector::from_words(64, &[0xA8F3B1900CC10FFF]);
+        let z = BinaryVector::from_words(96, &[0x883C385E66BD8704 ^ 0xA8F3B1900CC10FFF,
+                                               0xE43A5DF300000000]);
+        assert_eq!(x.bitxor_padded(&y), z);
+        assert_eq!(y.bitxor_padded(&x), z);
+    }
+
+    #[test]
+    fn test_weight() {
+        let x = BinaryVector { n: 8, data: vec![0x8300_0000_0000_0000] };
+        assert_eq!(x.weight(), 3);
+
+        let x = BinaryVector::from_words(96, &[0x883C385E66BD8704, 0xE43A5DF300000000]);
+        assert_eq!(x.weight(), x.to_bits().iter().fold(0, |acc, &b| acc + b as usize));
+
+        let x = BinaryVector { n: 4, data: vec![0xF000_0000_0000_0000] };
+        assert_eq!(x.weight(), 4);
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        let x = BinaryVector::from_bitstring("0000000100100011010001010110011110001001");
+        let y = BinaryVector::from_bitstring("0000000100100111010001010110011100001001");
+        assert_eq!(x.hamming_distance(&y), 2);
+        assert_eq!(x.hamming_distance(&x), 0);
+    }
+
+    #[test]
+    fn test_ones() {
+        let x = BinaryVector { n: 8, data: vec![0x8300_0000_0000_0000] };
+        assert_eq!(x.ones().collect::<Vec<usize>>(), vec![0, 6, 7]);
+
+        let x = BinaryVector::from_words(96, &[0x883C385E66BD8704, 0xE43A5DF300000000]);
+        let expected: Vec<usize> = x.to_bits().iter().enumerate()
+            .filter(|&(_, &b)| b == 1).map(|(i, _)| i).collect();
+        assert_eq!(x.ones().collect::<Vec<usize>>(), expected);
+
+        let x: BinaryVector = BinaryVector { n: 0, data: vec![] };
+        assert_eq!(x.ones().collect::<Vec<usize>>(), Vec::<usize>::new());
+    }
+
     #[test]
     fn test_to_bits() {
         // Test short vector
@@ -570,6 +1068,43 @@ mod tests {
         assert_eq!(x, z);
     }
 
+    #[test]
+    fn test_ord() {
+        let x = BinaryVector::from_bitstring("0100");
+        let y = BinaryVector::from_bitstring("0101");
+        let z = BinaryVector::from_bitstring("0110");
+        assert_eq!(x.cmp(&y), Ordering::Less);
+        assert_eq!(y.cmp(&x), Ordering::Greater);
+        assert_eq!(y.cmp(&z), Ordering::Less);
+
+        // A vector that's a prefix of a longer one orders first
+        let short = BinaryVector::from_bitstring("010");
+        let long = BinaryVector::from_bitstring("0100");
+        assert_eq!(short.cmp(&long), Ordering::Less);
+        assert_eq!(long.cmp(&short), Ordering::Greater);
+
+        // Equal vectors order equal, and sort into a BTreeSet without losing entries
+        let mut set = BTreeSet::new();
+        set.insert(z.clone());
+        set.insert(y.clone());
+        set.insert(x.clone());
+        set.insert(x.clone());
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), vec![x, y, z]);
+    }
+
+    #[test]
+    fn test_hash() {
+        let x = BinaryVector::from_bitstring("0000000100100011010001010110011110001001");
+        let y = BinaryVector::from_bitstring("0000000100100011010001010110011110001001");
+        let z = BinaryVector::from_bitstring("0000000100100011010001010110011110001000");
+
+        let mut set = HashSet::new();
+        set.insert(x.clone());
+        assert!(set.contains(&y));
+        assert!(!set.contains(&z));
+    }
+
     #[test]
     fn test_eq() {
         let x = BinaryVector::from_words(96, &[0x883C385E66BD8704, 0xE43A5DF300000000]);
@@ -0,0 +1,57 @@
+// Copyright 2017 Adam Greig
+
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use core::ops::{Shl, Shr};
+
+/// A fixed-width unsigned integer usable as the packed storage word of a `BinaryVector`.
+///
+/// Implemented for `u32` and `u64`, so vectors can be packed into whichever word size
+/// suits the target, including 32-bit and `no_std` embedded targets such as the
+/// baseband board itself.
+pub trait BitBlock:
+    Copy + Eq + Ord + core::hash::Hash
+    + BitAnd<Output=Self> + BitAndAssign
+    + BitOr<Output=Self> + BitOrAssign
+    + BitXor<Output=Self> + BitXorAssign
+    + Not<Output=Self>
+    + Shl<u32, Output=Self>
+    + Shr<u32, Output=Self>
+{
+    /// The number of bits held in one word of this type.
+    const BITS: u32;
+
+    /// The word with no bits set.
+    const ZERO: Self;
+
+    /// The word with only its least significant bit set.
+    const ONE: Self;
+
+    /// The word with every bit set.
+    const ONES: Self;
+
+    /// The number of set bits in this word.
+    fn count_ones(self) -> u32;
+
+    /// The number of leading zero bits, counting from the MSbit.
+    fn leading_zeros(self) -> u32;
+}
+
+impl BitBlock for u64 {
+    const BITS: u32 = 64;
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+    const ONES: Self = !0;
+
+    fn count_ones(self) -> u32 { u64::count_ones(self) }
+    fn leading_zeros(self) -> u32 { u64::leading_zeros(self) }
+}
+
+impl BitBlock for u32 {
+    const BITS: u32 = 32;
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+    const ONES: Self = !0;
+
+    fn count_ones(self) -> u32 { u32::count_ones(self) }
+    fn leading_zeros(self) -> u32 { u32::leading_zeros(self) }
+}
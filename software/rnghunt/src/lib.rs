@@ -1,13 +1,37 @@
 // Copyright 2017 Adam Greig
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Only `binary_vector` (and the `bitblock` word abstraction it builds on) is `no_std`.
+// The higher-level modules pull in `rand`/`println!`/etc and stay `std`-only so the
+// packed bit vectors themselves can still live directly in firmware.
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+
+// `#![no_std]` above implicitly declares `extern crate core;` for us, but under edition
+// 2015 the default `std`-feature build needs it spelled out to resolve `core::` paths
+// (used by `binary_vector`/`bitblock` to stay no_std-portable, and by `binary_polynomial`'s
+// `core::arch` intrinsics).
+#[cfg(feature = "std")]
+extern crate core;
+
+mod bitblock;
 mod binary_vector;
+#[cfg(feature = "std")]
 mod binary_matrix;
+#[cfg(feature = "std")]
 mod binary_polynomial;
+#[cfg(feature = "std")]
 mod berlekamp_massey;
 
-pub use binary_vector::BinaryVector;
+pub use bitblock::BitBlock;
+pub use binary_vector::{BinaryVector, BinaryVectorBlock, DecodeError};
+#[cfg(feature = "std")]
 pub use binary_matrix::BinaryMatrix;
+#[cfg(feature = "std")]
 pub use binary_polynomial::BinaryPolynomial;
+#[cfg(feature = "std")]
 pub use berlekamp_massey::berlekamp_massey;
 
 /// Compute the number of u64 words required to store n bits.